@@ -99,9 +99,10 @@
 //!
 //! The characters at the start of every row aren't present in actual encoded data.
 
-use crate::to_bytes::ToBytes;
+use crate::to_bytes::{FromBytes, ToBytes};
 use std::{
-    hint::unreachable_unchecked,
+    borrow::Cow,
+    iter::Peekable,
     str::{self, Utf8Error},
 };
 
@@ -149,59 +150,150 @@ pub fn encode_escape<T: ToBytes, S: AsRef<str>>(input: T, separator: S, escape:
     let data = input.to_bytes();
 
     let mut ret = String::with_capacity(data.len() * 6);
+    ret.extend(EncodeChars::new(data.iter().copied(), separator, escape));
+    ret
+}
 
-    for val in data {
-        let tail = val & 0b11;
-        let body = (val & 0b11111100) >> 2;
-
-        let fix = match tail {
-            0 => "",
-            1 => "*",
-            2 => "**",
-            3 => "***",
-            // SAFETY: `tail`` cannot be over 3 (0b11)
-            _ => unsafe { unreachable_unchecked() },
-        };
-        let fix2 = match tail {
-            0 => "",
-            1 => "\\*",
-            2 => "\\*\\*",
-            3 => "\\*\\*\\*",
-            // SAFETY: `tail`` cannot be over 3 (0b11)
-            _ => unsafe { unreachable_unchecked() },
-        };
-
-        // `&str`s are immutable, so we have to use a `&mut [u8]`
-        let line: &mut [u8] = &mut [0; 6];
-
-        line[0] = b'A' + (32 * (body & 1));
-        line[1] = b'N' + (32 * ((body >> 1) & 1));
-        line[2] = b'Y' + (32 * ((body >> 2) & 1));
-        line[3] = b'W' + (32 * ((body >> 3) & 1));
-        line[4] = b'A' + (32 * ((body >> 4) & 1));
-        line[5] = b'Y' + (32 * ((body >> 5) & 1));
-
-        // SAFETY: line only has valid ASCII characters
-        let line_str: &str = unsafe { str::from_utf8_unchecked(line) };
-
-        if escape {
-            ret.push_str(fix2);
-            ret.push_str(fix);
-            ret.push_str(line_str);
-            ret.push_str(fix);
-            ret.push_str(fix2);
+/// The position [`EncodeChars`] is at within the current word (or, if no word has been pulled
+/// from the byte source yet, [`Start`](EncodeState::Start)).
+///
+/// Each variant holds the index of the next character to emit for that part of the word, so
+/// resuming after a `next()` call only needs this enum plus the word's `stars`/`body`.
+#[derive(Clone, Copy, Debug)]
+enum EncodeState {
+    /// No word is loaded yet; the next byte (if any) should be pulled from the source.
+    Start,
+    /// Emitting the `*`/`\*` run before the "anyway" word.
+    EmitPrefix(u8),
+    /// Emitting the letters of the "anyway" word.
+    EmitLetter(u8),
+    /// Emitting the `*`/`\*` run after the "anyway" word.
+    EmitSuffix(u8),
+    /// Emitting the separator before the next word, at the given character index.
+    EmitSeparator(usize),
+}
+
+/// Lazily encodes a byte source into the `char`s of AES or EAES.
+///
+/// This yields one `char` at a time instead of building the whole `String` up front, so it can
+/// drive constant-memory encoding of large inputs or an `io::Write` bridge. [`encode`],
+/// [`encode_escaped`] and [`encode_escape`] are thin `.collect()` wrappers around this iterator.
+///
+/// For more information on the format, check the module's documentation.
+#[derive(Clone, Debug)]
+pub struct EncodeChars<'a, I: Iterator<Item = u8>> {
+    bytes: Peekable<I>,
+    separator: &'a str,
+    escape: bool,
+    state: EncodeState,
+    stars: u8,
+    body: u8,
+}
+
+impl<'a, I: Iterator<Item = u8>> EncodeChars<'a, I> {
+    /// Creates a new [`EncodeChars`] over `bytes`, using `separator` between words and escaping
+    /// the stars (producing EAES instead of AES) if `escape` is `true`.
+    ///
+    /// `separator` is used as-is; callers wanting the `check_separator` fallback should apply it
+    /// before calling this.
+    pub fn new(bytes: I, separator: &'a str, escape: bool) -> Self {
+        Self {
+            bytes: bytes.peekable(),
+            separator,
+            escape,
+            state: EncodeState::Start,
+            stars: 0,
+            body: 0,
+        }
+    }
+
+    /// The number of `*`/`\*` characters making up the prefix (or suffix) of the current word.
+    fn fix_len(&self) -> u8 {
+        if self.escape {
+            self.stars * 3
         } else {
-            ret.push_str(fix);
-            ret.push_str(line_str);
-            ret.push_str(fix);
+            self.stars
         }
+    }
 
-        ret.push_str(separator);
+    /// The `i`th character of the prefix, which is the escaped stars (`\*`) followed by the
+    /// plain stars (`*`) when `escape` is set, or just the plain stars otherwise.
+    fn prefix_char(&self, i: u8) -> char {
+        if self.escape && i < self.stars * 2 {
+            if i.is_multiple_of(2) {
+                '\\'
+            } else {
+                '*'
+            }
+        } else {
+            '*'
+        }
     }
-    for _ in 0..separator.len() {
-        ret.pop();
+
+    /// The `i`th character of the suffix, the mirror image of [`Self::prefix_char`]: plain stars
+    /// followed by escaped stars.
+    fn suffix_char(&self, i: u8) -> char {
+        if self.escape && i >= self.stars {
+            if (i - self.stars).is_multiple_of(2) {
+                '\\'
+            } else {
+                '*'
+            }
+        } else {
+            '*'
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = u8>> Iterator for EncodeChars<'a, I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            match self.state {
+                EncodeState::Start => {
+                    let val = self.bytes.next()?;
+                    self.stars = val & 0b11;
+                    self.body = (val & 0b1111_1100) >> 2;
+                    self.state = EncodeState::EmitPrefix(0);
+                }
+                EncodeState::EmitPrefix(i) => {
+                    if i < self.fix_len() {
+                        self.state = EncodeState::EmitPrefix(i + 1);
+                        return Some(self.prefix_char(i));
+                    }
+                    self.state = EncodeState::EmitLetter(0);
+                }
+                EncodeState::EmitLetter(i) => {
+                    if i < 6 {
+                        const BASE: [u8; 6] = [b'A', b'N', b'Y', b'W', b'A', b'Y'];
+                        let c = BASE[i as usize] + (32 * ((self.body >> i) & 1));
+                        self.state = EncodeState::EmitLetter(i + 1);
+                        return Some(c as char);
+                    }
+                    self.state = EncodeState::EmitSuffix(0);
+                }
+                EncodeState::EmitSuffix(i) => {
+                    if i < self.fix_len() {
+                        self.state = EncodeState::EmitSuffix(i + 1);
+                        return Some(self.suffix_char(i));
+                    }
+                    self.state = EncodeState::EmitSeparator(0);
+                }
+                EncodeState::EmitSeparator(i) => {
+                    // No separator before the very first word, nor after the last one.
+                    self.bytes.peek()?;
+                    match self.separator.chars().nth(i) {
+                        Some(c) => {
+                            self.state = EncodeState::EmitSeparator(i + 1);
+                            return Some(c);
+                        }
+                        None => self.state = EncodeState::Start,
+                    }
+                }
+            }
+        }
     }
-    ret
 }
 
 /// Decodes AES and EAES to a string.
@@ -224,69 +316,682 @@ pub fn decode_to_string(text: &str) -> Result<String, (Utf8Error, Vec<u8>)> {
 ///
 /// For more information, check the module's documentation.
 pub fn decode(text: &str) -> Vec<u8> {
-    let data = text.as_bytes();
+    DecodeBytes::new(text).collect()
+}
+
+/// Strips EAES's escaped stars (`\*`) out of `text`, leaving plain AES.
+///
+/// Only the first escape forces an allocation: if `text` contains no `\`, this returns
+/// [`Cow::Borrowed`] unchanged. Once an escape is found, a `String` is built once, copying the
+/// untouched spans between escapes.
+///
+/// The key invariant is that `\` followed by any character collapses to nothing (the stars EAES
+/// wraps it around are redundant decoration); a trailing lone `\` with nothing left to escape is
+/// preserved verbatim. This is a standalone normalization helper for callers who want plain AES
+/// text out of EAES input without decoding it; [`decode`] doesn't use it, since [`DecodeBytes`]
+/// already understands `\` directly.
+pub fn unescape(text: &str) -> Cow<'_, str> {
+    let Some(first) = text.find('\\') else {
+        return Cow::Borrowed(text);
+    };
 
-    let mut body_idx: u8 = 0;
-    let mut body: u8 = 0;
-    let mut stars: u8 = 0;
+    let bytes = text.as_bytes();
+    let mut result = String::with_capacity(text.len());
+    result.push_str(&text[..first]);
 
-    let mut ret = Vec::new();
+    let mut idx = first;
+    while idx < bytes.len() {
+        if bytes[idx] == b'\\' {
+            if let Some(next) = text[idx + 1..].chars().next() {
+                idx += 1 + next.len_utf8();
+            } else {
+                result.push('\\');
+                idx += 1;
+            }
+        } else {
+            let start = idx;
+            while idx < bytes.len() && bytes[idx] != b'\\' {
+                idx += 1;
+            }
+            result.push_str(&text[start..idx]);
+        }
+    }
+
+    Cow::Owned(result)
+}
+
+/// Decodes AES or EAES `text` and reconstructs a `T` from the resulting bytes via [`FromBytes`].
+///
+/// For more information, check the module's documentation.
+pub fn decode_as<T: FromBytes>(text: &str) -> Result<T, crate::to_bytes::FromBytesError> {
+    T::from_bytes(decode(text))
+}
+
+/// Decodes WTF-8 `bytes` back into UTF-16 code units, reversing
+/// [`encode_wtf8_units`](crate::to_bytes::encode_wtf8_units): ordinary UTF-8 sequences decode to
+/// their code point's UTF-16 form, and a three-byte sequence encoding an unpaired surrogate
+/// decodes straight back to that surrogate instead of being rejected.
+///
+/// Pulled out of [`from_anyway_os_string`] so the surrogate math has no dependency on actually
+/// being on Windows, and so it can be unit-tested on any platform.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) fn decode_wtf8_units(bytes: &[u8]) -> Vec<u16> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let b0 = bytes[idx];
+        if b0 < 0x80 {
+            units.push(b0 as u16);
+            idx += 1;
+        } else if b0 & 0xE0 == 0xC0 && idx + 1 < bytes.len() {
+            let cp = ((b0 as u32 & 0x1F) << 6) | (bytes[idx + 1] as u32 & 0x3F);
+            units.push(cp as u16);
+            idx += 2;
+        } else if b0 & 0xF0 == 0xE0 && idx + 2 < bytes.len() {
+            let cp = ((b0 as u32 & 0x0F) << 12)
+                | ((bytes[idx + 1] as u32 & 0x3F) << 6)
+                | (bytes[idx + 2] as u32 & 0x3F);
+            units.push(cp as u16);
+            idx += 3;
+        } else if b0 & 0xF8 == 0xF0 && idx + 3 < bytes.len() {
+            let cp = ((b0 as u32 & 0x07) << 18)
+                | ((bytes[idx + 1] as u32 & 0x3F) << 12)
+                | ((bytes[idx + 2] as u32 & 0x3F) << 6)
+                | (bytes[idx + 3] as u32 & 0x3F);
+            let cp = cp - 0x10000;
+            units.push(0xD800 + (cp >> 10) as u16);
+            units.push(0xDC00 + (cp & 0x3FF) as u16);
+            idx += 4;
+        } else {
+            idx += 1;
+        }
+    }
+    units
+}
 
-    let mut idx: usize = 0;
+/// Reconstructs an [`OsString`] from bytes produced by [`ToBytes::to_bytes`] on an
+/// [`OsStr`](std::ffi::OsStr)/[`Path`](std::path::Path), reversing its WTF-8 encoding.
+///
+/// On Unix this is a plain wrap, since `OsStr` there is already an arbitrary byte string. On
+/// Windows the WTF-8 bytes (including any three-byte unpaired-surrogate sequences) are decoded
+/// back into UTF-16 code units.
+pub fn from_anyway_os_string(bytes: Vec<u8>) -> std::ffi::OsString {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        std::ffi::OsString::from_vec(bytes)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStringExt;
+        std::ffi::OsString::from_wide(&decode_wtf8_units(&bytes))
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        std::ffi::OsString::from(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// Set on a [`CLASS`] entry when the byte takes part in AES/EAES at all (one of the `anyway`
+/// letters in either case, `*`, or `\`).
+const SIGNIFICANT: u8 = 0b0001;
+/// Set on a [`CLASS`] entry for `*`.
+const STAR: u8 = 0b0010;
+/// Set on a [`CLASS`] entry for `\`.
+const BACKSLASH: u8 = 0b0100;
+/// Set on a [`CLASS`] entry for a lowercase `anyway` letter, i.e. one that sets a body bit.
+const LOWERCASE_LETTER: u8 = 0b1000;
+
+/// Classifies a single byte for [`DecodeBytes`], packing the bits above into one lookup.
+const fn classify(byte: u8) -> u8 {
+    match byte {
+        b'a' | b'n' | b'y' | b'w' | b'A' | b'N' | b'Y' | b'W' => {
+            if byte >= 96 {
+                SIGNIFICANT | LOWERCASE_LETTER
+            } else {
+                SIGNIFICANT
+            }
+        }
+        b'*' => SIGNIFICANT | STAR,
+        b'\\' => SIGNIFICANT | BACKSLASH,
+        _ => 0,
+    }
+}
+
+/// A 256-entry table mapping every byte value to its [`classify`] flags, built once at compile
+/// time so [`DecodeBytes`] never has to re-derive a byte's significance at runtime.
+const CLASS: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut byte = 0usize;
+    while byte < table.len() {
+        table[byte] = classify(byte as u8);
+        byte += 1;
+    }
+    table
+};
+
+/// Lazily decodes AES and EAES `text` into the bytes it represents.
+///
+/// This yields one `u8` at a time instead of building the whole `Vec` up front, so it can drive
+/// constant-memory decoding of large inputs or an `io::Read` bridge. [`decode`] is a thin
+/// `.collect()` wrapper around this iterator.
+///
+/// A `\` is treated the same way EAES's escape works: it and the byte after it are both dropped
+/// without affecting the word in progress, so escaped stars (and any other byte a lossy channel
+/// mangled a `\` into pairing with) vanish cleanly instead of being misread as word content.
+///
+/// For more information on the format, check the module's documentation.
+#[derive(Clone, Debug)]
+pub struct DecodeBytes<'a> {
+    data: &'a [u8],
+    idx: usize,
+    body_idx: u8,
+    body: u8,
+    stars: u8,
+}
+
+impl<'a> DecodeBytes<'a> {
+    /// Creates a new [`DecodeBytes`] over `text`.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            data: text.as_bytes(),
+            idx: 0,
+            body_idx: 0,
+            body: 0,
+            stars: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for DecodeBytes<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        while self.idx < self.data.len() {
+            while self.idx < self.data.len()
+                && CLASS[self.data[self.idx] as usize] & SIGNIFICANT == 0
+            {
+                self.idx += 1
+            }
+            if self.idx == self.data.len() {
+                break;
+            }
+            let class = CLASS[self.data[self.idx] as usize];
+
+            if class & BACKSLASH != 0 {
+                // Mirrors EAES's `\*` escape: the backslash and whatever follows it (the star
+                // it's escaping, or anything else a lossy channel mangled it into) are both
+                // dropped rather than counted as part of the word.
+                self.idx += 2;
+                continue;
+            }
+
+            if class & STAR != 0 {
+                self.stars += 1;
+            } else {
+                if class & LOWERCASE_LETTER != 0 {
+                    self.body += 1 << self.body_idx;
+                }
+                self.body_idx += 1;
+            }
+
+            #[allow(unused_assignments)]
+            if self.body_idx == 6 {
+                self.body_idx = 0;
+                self.stars %= 4; // eradicate potential edge cases
+
+                let value = (self.body << 2) + self.stars;
+
+                while self.idx < self.data.len()
+                    && CLASS[self.data[self.idx] as usize] & SIGNIFICANT != 0
+                {
+                    self.idx += 1
+                }
+
+                self.body = 0;
+                self.stars = 0;
+
+                return Some(value);
+            }
+
+            self.idx += 1;
+        }
+
+        None
+    }
+}
+
+/// An error produced by [`decode_strict`], reporting both what went wrong and where.
+///
+/// Modeled on RON's `SpannedError`/`Position`: the `position` is the byte offset into the input
+/// text at which the fault was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    /// The byte offset into the input at which the fault was found.
+    pub position: usize,
+    /// What kind of fault was found.
+    pub kind: DecodeErrorKind,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at byte offset {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// The kind of fault [`decode_strict`] can report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    /// The input ended partway through a word's 6 letters.
+    TruncatedWord,
+    /// A byte that can't appear where it was found, such as a fourth `*` in a star run.
+    UnexpectedChar,
+    /// A `\` at the end of input with no character left for it to escape.
+    DanglingEscape,
+}
+
+impl std::fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DecodeErrorKind::TruncatedWord => "truncated word",
+            DecodeErrorKind::UnexpectedChar => "unexpected character",
+            DecodeErrorKind::DanglingEscape => "dangling escape",
+        })
+    }
+}
+
+/// Decodes AES and EAES to a vector, rejecting malformed input instead of silently producing
+/// the wrong bytes.
+///
+/// Unlike [`decode`], which tolerates (and silently mis-decodes) truncated words, star runs of
+/// more than three, and dangling escapes, this rejects them with a [`DecodeError`] that reports
+/// the byte offset and kind of the fault. Use this to validate data that round-tripped through a
+/// lossy channel (like Discord) before trusting it.
+///
+/// Stars are only checked for the length of each run: a star between a word's letters counts
+/// toward that word's tail, and the run after a word is not compared with the run before it.
+///
+/// For more information on the format, check the module's documentation.
+pub fn decode_strict(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let data = text.as_bytes();
+    let mut idx = 0usize;
+    let mut ret = Vec::new();
 
     while idx < data.len() {
-        while idx < data.len()
-            && !matches!(
-                data[idx],
-                b'a' | b'n' | b'y' | b'w' | b'A' | b'N' | b'Y' | b'W' | b'*' | b'\\'
-            )
-        {
-            idx += 1
+        while idx < data.len() && CLASS[data[idx] as usize] & SIGNIFICANT == 0 {
+            idx += 1;
         }
         if idx == data.len() {
             break;
         }
-        let val = data[idx];
 
-        match val {
-            b'\\' => {
+        let word_start = idx;
+        let mut body_idx: u8 = 0;
+        let mut body: u8 = 0;
+        let mut stars: u8 = 0;
+
+        while body_idx < 6 {
+            while idx < data.len() && CLASS[data[idx] as usize] & SIGNIFICANT == 0 {
+                idx += 1;
+            }
+            if idx == data.len() {
+                return Err(DecodeError {
+                    position: word_start,
+                    kind: DecodeErrorKind::TruncatedWord,
+                });
+            }
+
+            let class = CLASS[data[idx] as usize];
+            if class & BACKSLASH != 0 {
+                if idx + 1 >= data.len() {
+                    return Err(DecodeError {
+                        position: idx,
+                        kind: DecodeErrorKind::DanglingEscape,
+                    });
+                }
+                idx += 2;
+            } else if class & STAR != 0 {
+                stars += 1;
+                if stars > 3 {
+                    return Err(DecodeError {
+                        position: idx,
+                        kind: DecodeErrorKind::UnexpectedChar,
+                    });
+                }
+                idx += 1;
+            } else {
+                if class & LOWERCASE_LETTER != 0 {
+                    body += 1 << body_idx;
+                }
+                body_idx += 1;
+                idx += 1;
+            }
+        }
+
+        ret.push((body << 2) + stars);
+
+        // Consume (and validate) the star run after the word, mirroring `decode`'s trailing
+        // skip, but stop at the next letter instead of swallowing it as part of this word.
+        let mut suffix_stars = 0u8;
+        while idx < data.len() {
+            let class = CLASS[data[idx] as usize];
+            if class & BACKSLASH != 0 {
+                if idx + 1 >= data.len() {
+                    return Err(DecodeError {
+                        position: idx,
+                        kind: DecodeErrorKind::DanglingEscape,
+                    });
+                }
+                idx += 2;
+            } else if class & STAR != 0 {
+                suffix_stars += 1;
+                if suffix_stars > 3 {
+                    return Err(DecodeError {
+                        position: idx,
+                        kind: DecodeErrorKind::UnexpectedChar,
+                    });
+                }
+                idx += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_drops_truncated_trailing_escape() {
+        // A lone `\` landing on what would be the word's 6th letter escapes (and so drops)
+        // whatever comes after it, including the implicit end of input; the half-built word
+        // never completes and contributes nothing.
+        assert_eq!(decode("ANYWA\\"), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn decode_escape_does_not_swallow_the_next_word() {
+        // The `\` here escapes only the `.` right after it; the word that follows must still
+        // decode normally instead of being eaten along with the escape.
+        assert_eq!(decode("WaYawA\\.wyyWnY"), vec![104, 92]);
+    }
+
+    /// A tiny deterministic xorshift PRNG, used instead of a `rand` dependency so the fuzz test
+    /// below is fully reproducible across runs.
+    struct Xorshift64(u64);
+
+    impl Xorshift64 {
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    /// A direct port of AES/EAES's documented decode semantics: `\` always escapes (and so
+    /// drops) itself plus exactly the next byte, everywhere, including mid-word and in the
+    /// trailing star run after a word completes. [`DecodeBytes`] must agree with this byte for
+    /// byte for every input, including the malformed/mangled ones a lossy channel can produce.
+    fn reference_decode(text: &str) -> Vec<u8> {
+        let data = text.as_bytes();
+        let mut idx = 0;
+        let mut body_idx: u8 = 0;
+        let mut body: u8 = 0;
+        let mut stars: u8 = 0;
+        let mut ret = Vec::new();
+
+        while idx < data.len() {
+            while idx < data.len() && CLASS[data[idx] as usize] & SIGNIFICANT == 0 {
+                idx += 1;
+            }
+            if idx == data.len() {
+                break;
+            }
+
+            let class = CLASS[data[idx] as usize];
+            if class & BACKSLASH != 0 {
                 idx += 2;
                 continue;
             }
-            b'*' => stars += 1,
 
-            96.. => {
-                body += 1 << body_idx;
+            if class & STAR != 0 {
+                stars += 1;
+            } else {
+                if class & LOWERCASE_LETTER != 0 {
+                    body += 1 << body_idx;
+                }
                 body_idx += 1;
             }
-            ..=95 => body_idx += 1,
-        }
 
-        #[allow(unused_assignments)]
-        if body_idx == 6 {
-            body_idx = 0;
-            stars %= 4; // eradicate potential edge cases
+            if body_idx == 6 {
+                body_idx = 0;
+                stars %= 4;
+                ret.push((body << 2) + stars);
 
-            let value = (body << 2) + stars;
-            ret.push(value);
+                while idx < data.len() && CLASS[data[idx] as usize] & SIGNIFICANT != 0 {
+                    idx += 1;
+                }
 
-            while idx < data.len()
-                && matches!(
-                    data[idx],
-                    b'a' | b'n' | b'y' | b'w' | b'A' | b'N' | b'Y' | b'W' | b'*' | b'\\'
-                )
-            {
-                idx += 1
+                body = 0;
+                stars = 0;
+                continue;
             }
 
-            body = 0;
-            stars = 0;
+            idx += 1;
+        }
 
-            continue;
+        ret
+    }
+
+    #[test]
+    fn decode_matches_reference_semantics_on_random_input() {
+        // Differential test: `decode` must agree with `reference_decode` on every input,
+        // including ones with stray/misplaced `\`, not just well-formed EAES. This is the
+        // class of input that previously escaped through `unescape`'s context-free pairing and
+        // `DecodeBytes` no longer special-casing `\` at all.
+        const ALPHABET: &[u8] = b"anywANYW*\\.,; 0123456789";
+
+        let mut rng = Xorshift64(0x9E3779B97F4A7C15);
+        for _ in 0..20_000 {
+            let len = (rng.next_u64() % 24) as usize;
+            let s: String = (0..len)
+                .map(|_| ALPHABET[(rng.next_u64() as usize) % ALPHABET.len()] as char)
+                .collect();
+
+            assert_eq!(decode(&s), reference_decode(&s), "mismatch for input {s:?}");
+        }
+    }
+
+    #[test]
+    fn decode_round_trips_through_encode_escaped() {
+        for bytes in [
+            Vec::new(),
+            vec![0u8],
+            vec![255u8],
+            b"Hello, world!".to_vec(),
+            (0..=255u8).collect(),
+        ] {
+            let encoded = encode_escaped(&bytes[..], ", ");
+            assert_eq!(decode(&encoded), bytes);
+        }
+    }
+
+    #[test]
+    fn decode_strict_accepts_valid_input_and_matches_decode() {
+        for bytes in [
+            Vec::new(),
+            vec![0u8],
+            vec![255u8],
+            b"Hello, world!".to_vec(),
+            (0..=255u8).collect(),
+        ] {
+            let encoded = encode_escaped(&bytes[..], ", ");
+            assert_eq!(decode_strict(&encoded), Ok(bytes.clone()));
+            assert_eq!(decode_strict(&encoded).unwrap(), decode(&encoded));
         }
+    }
 
-        idx += 1;
+    #[test]
+    fn decode_strict_rejects_truncated_word() {
+        // Only 4 of the 6 letters are present before the input ends.
+        let err = decode_strict("ANYW").unwrap_err();
+        assert_eq!(err.kind, DecodeErrorKind::TruncatedWord);
+        assert_eq!(err.position, 0);
     }
 
-    ret
+    #[test]
+    fn decode_strict_rejects_unexpected_char_on_excess_stars() {
+        // A 4th prefix star is never valid; the tail only has room for 0-3.
+        let err = decode_strict("****ANYWAY").unwrap_err();
+        assert_eq!(err.kind, DecodeErrorKind::UnexpectedChar);
+        assert_eq!(err.position, 3);
+    }
+
+    #[test]
+    fn decode_strict_rejects_dangling_escape_mid_word() {
+        let err = decode_strict("ANYWA\\").unwrap_err();
+        assert_eq!(err.kind, DecodeErrorKind::DanglingEscape);
+        assert_eq!(err.position, 5);
+    }
+
+    #[test]
+    fn decode_strict_rejects_dangling_escape_in_trailing_stars() {
+        let err = decode_strict("*ANYWAY*\\").unwrap_err();
+        assert_eq!(err.kind, DecodeErrorKind::DanglingEscape);
+        assert_eq!(err.position, 8);
+    }
+
+    #[test]
+    fn decode_strict_never_panics_on_random_input() {
+        // Fuzz test: these strings are not guaranteed to be well-formed AES/EAES, and
+        // `decode_strict` must return (`Ok` or `Err`) for every one of them instead of
+        // panicking on an out-of-bounds index or similar.
+        const ALPHABET: &[u8] = b"anywANYW*\\.,; 0123456789";
+
+        let mut rng = Xorshift64(0xD1B54A32D192ED03);
+        for _ in 0..20_000 {
+            let len = (rng.next_u64() % 24) as usize;
+            let s: String = (0..len)
+                .map(|_| ALPHABET[(rng.next_u64() as usize) % ALPHABET.len()] as char)
+                .collect();
+
+            let _ = decode_strict(&s);
+        }
+    }
+
+    #[test]
+    fn from_anyway_os_string_round_trips_unix_os_str() {
+        use crate::to_bytes::ToBytes;
+        use std::ffi::{OsStr, OsString};
+
+        for s in [
+            "hello",
+            "",
+            "unicode: héllo 😀",
+            "with, separators; and * stars \\ backslashes",
+        ] {
+            let bytes = OsStr::new(s).to_bytes().into_owned();
+            assert_eq!(from_anyway_os_string(bytes), OsString::from(s));
+        }
+    }
+
+    #[test]
+    fn decode_wtf8_units_decodes_ascii_and_multibyte_utf8() {
+        assert_eq!(decode_wtf8_units(b"Hi"), vec![0x0048, 0x0069]);
+        assert_eq!(decode_wtf8_units(&[0xC3, 0xA9]), vec![0x00E9]); // é, 2-byte UTF-8
+        assert_eq!(decode_wtf8_units(&[0xE2, 0x82, 0xAC]), vec![0x20AC]); // €, 3-byte UTF-8
+        // 😀 (U+1F600), a 4-byte UTF-8 sequence that decodes to a UTF-16 surrogate pair.
+        assert_eq!(
+            decode_wtf8_units(&[0xF0, 0x9F, 0x98, 0x80]),
+            vec![0xD83D, 0xDE00]
+        );
+    }
+
+    #[test]
+    fn decode_wtf8_units_decodes_unpaired_surrogate_three_byte_form() {
+        // An unpaired high surrogate (0xD800) has no valid UTF-8 form, so WTF-8 encodes it as a
+        // three-byte sequence that looks like (invalid) UTF-8 for that code point.
+        assert_eq!(decode_wtf8_units(&[0xED, 0xA0, 0x80]), vec![0xD800u16]);
+    }
+
+    #[test]
+    fn encode_then_decode_wtf8_units_round_trips_unpaired_surrogate() {
+        use crate::to_bytes::encode_wtf8_units;
+
+        let units = [0xD800u16];
+        let bytes = encode_wtf8_units(units.iter().copied());
+        assert_eq!(bytes, vec![0xED, 0xA0, 0x80]);
+        assert_eq!(decode_wtf8_units(&bytes), units.to_vec());
+    }
+
+    #[test]
+    fn unescape_borrows_when_there_is_no_backslash() {
+        let input = "anywANYW*, no escapes here";
+        match unescape(input) {
+            Cow::Borrowed(s) => assert_eq!(s, input),
+            Cow::Owned(_) => panic!("expected the borrowed fast path"),
+        }
+    }
+
+    #[test]
+    fn unescape_collapses_escape_pairs() {
+        assert_eq!(unescape("a\\*b"), "ab");
+        assert_eq!(unescape("\\*\\*plain\\*\\*"), "plain");
+    }
+
+    #[test]
+    fn unescape_preserves_a_trailing_lone_backslash() {
+        assert_eq!(unescape("abc\\"), "abc\\");
+    }
+
+    #[test]
+    fn unescape_matches_reference_semantics_on_random_input() {
+        // Independently restates the invariant from `unescape`'s doc comment (`\` plus the next
+        // character collapses to nothing; a trailing lone `\` survives) byte-by-byte over an
+        // ASCII-only alphabet, where a byte and a character coincide, rather than
+        // copying its range-copying implementation, so this actually exercises the fast/slow
+        // path split instead of just re-deriving it.
+        fn reference_unescape(text: &str) -> String {
+            let bytes = text.as_bytes();
+            let mut result = String::new();
+            let mut idx = 0;
+            while idx < bytes.len() {
+                if bytes[idx] == b'\\' {
+                    if idx + 1 < bytes.len() {
+                        idx += 2;
+                    } else {
+                        result.push('\\');
+                        idx += 1;
+                    }
+                } else {
+                    result.push(bytes[idx] as char);
+                    idx += 1;
+                }
+            }
+            result
+        }
+
+        const ALPHABET: &[u8] = b"anywANYW*\\.,; 0123456789";
+
+        let mut rng = Xorshift64(0xC2B2AE3D27D4EB4F);
+        for _ in 0..5_000 {
+            let len = (rng.next_u64() % 24) as usize;
+            let s: String = (0..len)
+                .map(|_| ALPHABET[(rng.next_u64() as usize) % ALPHABET.len()] as char)
+                .collect();
+
+            assert_eq!(
+                unescape(&s),
+                reference_unescape(&s),
+                "mismatch for input {s:?}"
+            );
+        }
+    }
 }
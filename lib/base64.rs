@@ -0,0 +1,190 @@
+//! # Base64 Encosure Scheme
+//!
+//! This encosure scheme base64-encodes the payload first, then treats base64's own 64-symbol
+//! alphabet as the encosure: there's no per-byte word to build, just a lookup into the alphabet
+//! (and back). It's a far more compact alternative to [`anyway`](crate::anyway), at the cost of
+//! the "anyway" words' human readability.
+//!
+//! # Examples
+//!
+//! The string "Hi" would look like:
+//! ```text
+//! SGk=
+//! ```
+
+use crate::to_bytes::ToBytes;
+use std::str::{self, Utf8Error};
+
+/// The 64 symbols of the standard base64 alphabet, indexed by 6-bit value.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes input (in bytes) to base64.
+///
+/// For more information, check the module's documentation.
+pub fn encode<T: ToBytes>(input: T) -> String {
+    let data = input.to_bytes();
+
+    let mut ret = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+
+        let n = ((b0 as u32) << 16) | ((b1 as u32) << 8) | (b2 as u32);
+
+        ret.push(ALPHABET[((n >> 18) & 0x3F) as usize] as char);
+        ret.push(ALPHABET[((n >> 12) & 0x3F) as usize] as char);
+        ret.push(if chunk.len() > 1 {
+            ALPHABET[((n >> 6) & 0x3F) as usize] as char
+        } else {
+            '='
+        });
+        ret.push(if chunk.len() > 2 {
+            ALPHABET[(n & 0x3F) as usize] as char
+        } else {
+            '='
+        });
+    }
+    ret
+}
+
+/// Maps a byte to its 6-bit base64 value, or `-1` if it isn't part of the alphabet.
+const fn classify(byte: u8) -> i8 {
+    match byte {
+        b'A'..=b'Z' => (byte - b'A') as i8,
+        b'a'..=b'z' => (byte - b'a') as i8 + 26,
+        b'0'..=b'9' => (byte - b'0') as i8 + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => -1,
+    }
+}
+
+/// A 256-entry table mapping every byte value to its [`classify`] result, built once at compile
+/// time the same way `anyway`'s decoder classifies its own alphabet.
+const CLASS: [i8; 256] = {
+    let mut table = [-1i8; 256];
+    let mut byte = 0usize;
+    while byte < table.len() {
+        table[byte] = classify(byte as u8);
+        byte += 1;
+    }
+    table
+};
+
+/// Decodes base64 to a vector.
+///
+/// Bytes outside the base64 alphabet (including `=` padding) are skipped rather than rejected.
+///
+/// For more information, check the module's documentation.
+pub fn decode(text: &str) -> Vec<u8> {
+    let mut ret = Vec::with_capacity(text.len() / 4 * 3);
+
+    let mut buf: u32 = 0;
+    let mut bits: u32 = 0;
+    for &byte in text.as_bytes() {
+        let value = CLASS[byte as usize];
+        if value < 0 {
+            continue;
+        }
+
+        buf = (buf << 6) | (value as u32);
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            ret.push((buf >> bits) as u8);
+        }
+    }
+
+    ret
+}
+
+/// Decodes base64 to a string.
+///
+/// Returns an error alongside a vector with the decoded data if it can't be constructed into a [`String`].
+///
+/// For more information, check the module's documentation.
+pub fn decode_to_string(text: &str) -> Result<String, (Utf8Error, Vec<u8>)> {
+    let vec = decode(text);
+
+    let string = str::from_utf8(&vec);
+    match string {
+        // SAFETY: `from_utf8` is performer earlier
+        Ok(_) => unsafe { Ok(String::from_utf8_unchecked(vec)) },
+        Err(e) => Err((e, vec)),
+    }
+}
+
+/// Decodes base64 `text` and reconstructs a `T` from the resulting bytes via
+/// [`FromBytes`](crate::to_bytes::FromBytes).
+///
+/// For more information, check the module's documentation.
+pub fn decode_as<T: crate::to_bytes::FromBytes>(
+    text: &str,
+) -> Result<T, crate::to_bytes::FromBytesError> {
+    T::from_bytes(decode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A tiny deterministic xorshift PRNG, used instead of a `rand` dependency so the fuzz test
+    /// below is fully reproducible across runs.
+    struct Xorshift64(u64);
+
+    impl Xorshift64 {
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    #[test]
+    fn encode_matches_known_vector() {
+        assert_eq!(encode("Hi"), "SGk=");
+        assert_eq!(encode(""), "");
+        assert_eq!(encode("Hello, world!"), "SGVsbG8sIHdvcmxkIQ==");
+    }
+
+    #[test]
+    fn decode_matches_known_vector() {
+        assert_eq!(decode("SGk="), b"Hi");
+        assert_eq!(decode("SGVsbG8sIHdvcmxkIQ=="), b"Hello, world!");
+    }
+
+    #[test]
+    fn decode_skips_bytes_outside_the_alphabet() {
+        // Whitespace, padding, and other noise mixed into otherwise-valid base64 are ignored
+        // rather than rejected, per this module's documented decode behavior.
+        assert_eq!(decode("SG k=\n"), b"Hi");
+        assert_eq!(decode("***SGk=***"), b"Hi");
+    }
+
+    #[test]
+    fn decode_to_string_rejects_invalid_utf8() {
+        // "/w==" decodes to the single byte 0xFF, which isn't valid UTF-8 on its own.
+        let (_, bytes) = decode_to_string("/w==").unwrap_err();
+        assert_eq!(bytes, vec![0xFF]);
+    }
+
+    #[test]
+    fn decode_as_reconstructs_via_from_bytes() {
+        let s: String = decode_as(&encode("round trip")).unwrap();
+        assert_eq!(s, "round trip");
+    }
+
+    #[test]
+    fn decode_round_trips_through_encode_on_random_input() {
+        let mut rng = Xorshift64(0x243F6A8885A308D3);
+        for _ in 0..5_000 {
+            let len = (rng.next_u64() % 40) as usize;
+            let bytes: Vec<u8> = (0..len).map(|_| rng.next_u64() as u8).collect();
+
+            assert_eq!(decode(&encode(&bytes[..])), bytes, "mismatch for {bytes:?}");
+        }
+    }
+}
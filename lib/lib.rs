@@ -5,4 +5,5 @@
 //! Here is the original repo with all the docs: <https://github.com/TadaHrd/arsenal-of-encosure-schemes-AES>
 
 pub mod anyway;
+pub mod base64;
 pub mod to_bytes;
@@ -1,57 +1,250 @@
 //! # To Bytes
 //!
-//! This module features the [`ToBytes`] trait. That's it.
+//! This module features the [`ToBytes`] trait and its inverse, [`FromBytes`].
 
-use std::str;
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
 
 /// This trait is used in the encosure schemes as the type of `input`.
 ///
-/// It provides the `to_bytes` function that turns the value provided into a `&[u8]`.
+/// It provides the `to_bytes` function that turns the value provided into a `Cow<[u8]>`.
 pub trait ToBytes {
-    /// This function turns `&self` into `&[u8]` (a slice of bytes).
+    /// This function turns `&self` into a `Cow<[u8]>` (a possibly-owned slice of bytes).
+    ///
+    /// Most implementations borrow straight from `self`; ones that need to transcode (like
+    /// [`OsStr`]'s WTF-8 encoding on Windows) allocate an owned buffer instead.
     ///
     /// # Examples
     ///
     /// ```rust
     /// # use aes_rust::to_bytes::ToBytes;
-    /// assert_eq!("ABC".to_bytes(), [65, 66, 67]);
-    /// assert_eq!([0, 1, 2, 3].to_bytes(), [0, 1, 2, 3]);
+    /// assert_eq!(&"ABC".to_bytes()[..], &[65, 66, 67][..]);
+    /// assert_eq!(&[0, 1, 2, 3].to_bytes()[..], &[0, 1, 2, 3][..]);
     /// ```
-    fn to_bytes(&self) -> &[u8];
+    fn to_bytes(&self) -> Cow<'_, [u8]>;
 }
 
 impl ToBytes for &str {
-    fn to_bytes(&self) -> &[u8] {
-        self.as_bytes()
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
     }
 }
 
 impl ToBytes for String {
-    fn to_bytes(&self) -> &[u8] {
-        self.as_bytes()
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
     }
 }
 
 impl<const N: usize> ToBytes for [u8; N] {
-    fn to_bytes(&self) -> &[u8] {
-        self
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
     }
 }
 impl<'a, const N: usize> ToBytes for &'a [u8; N] {
-    fn to_bytes(&self) -> &[u8] {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
         #[allow(clippy::explicit_auto_deref)]
-        *self
+        Cow::Borrowed(*self)
     }
 }
 
 impl ToBytes for &[u8] {
-    fn to_bytes(&self) -> &[u8] {
-        self
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
     }
 }
 
 impl ToBytes for Vec<u8> {
-    fn to_bytes(&self) -> &[u8] {
-        self
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+/// Encodes a UTF-16 code unit sequence as WTF-8 bytes: valid UTF-16 produces ordinary UTF-8,
+/// while an unpaired surrogate is encoded as its three-byte WTF-8 form instead of being rejected
+/// like regular UTF-8 would.
+///
+/// Pulled out of [`encode_wtf8`] so the surrogate math has no dependency on actually being on
+/// Windows, and so it can be unit-tested on any platform.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) fn encode_wtf8_units(units: impl Iterator<Item = u16>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for unit in char::decode_utf16(units) {
+        match unit {
+            Ok(c) => {
+                let mut tmp = [0u8; 4];
+                buf.extend_from_slice(c.encode_utf8(&mut tmp).as_bytes());
+            }
+            Err(err) => {
+                // An unpaired surrogate has no valid UTF-8 form; encode its code point as a
+                // three-byte sequence anyway, as WTF-8 does.
+                let surrogate = err.unpaired_surrogate() as u32;
+                buf.push(0xE0 | (surrogate >> 12) as u8);
+                buf.push(0x80 | ((surrogate >> 6) & 0x3F) as u8);
+                buf.push(0x80 | (surrogate & 0x3F) as u8);
+            }
+        }
+    }
+    buf
+}
+
+/// Encodes `value` as WTF-8: valid UTF-8 passes through unchanged, while an unpaired UTF-16
+/// surrogate is encoded as its three-byte WTF-8 form instead of being rejected like regular
+/// UTF-8 would.
+#[cfg(windows)]
+fn encode_wtf8(value: &OsStr) -> Vec<u8> {
+    use std::os::windows::ffi::OsStrExt;
+    encode_wtf8_units(value.encode_wide())
+}
+
+impl ToBytes for OsStr {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            Cow::Borrowed(self.as_bytes())
+        }
+        #[cfg(windows)]
+        {
+            Cow::Owned(encode_wtf8(self))
+        }
+        #[cfg(not(any(unix, windows)))]
+        {
+            Cow::Owned(self.to_string_lossy().into_owned().into_bytes())
+        }
+    }
+}
+
+impl ToBytes for OsString {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        self.as_os_str().to_bytes()
+    }
+}
+
+impl ToBytes for Path {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        self.as_os_str().to_bytes()
+    }
+}
+
+impl ToBytes for PathBuf {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        self.as_path().to_bytes()
+    }
+}
+
+/// An error produced by [`FromBytes::from_bytes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FromBytesError {
+    /// The bytes weren't valid UTF-8, so they can't become a [`String`].
+    InvalidUtf8(std::str::Utf8Error),
+    /// The bytes weren't the length the target type needed, such as a fixed-size array.
+    WrongLength {
+        /// The length the target type requires.
+        expected: usize,
+        /// The length the decoded bytes actually had.
+        got: usize,
+    },
+}
+
+impl std::fmt::Display for FromBytesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FromBytesError::InvalidUtf8(e) => write!(f, "invalid UTF-8: {e}"),
+            FromBytesError::WrongLength { expected, got } => {
+                write!(f, "expected {expected} bytes, got {got}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FromBytesError {}
+
+/// The inverse of [`ToBytes`]: reconstructs a typed value from the bytes an encosure scheme
+/// decoded, mirroring the old `ToBase64`/`FromBase64` trait pair.
+pub trait FromBytes: Sized {
+    /// Turns decoded `bytes` back into `Self`, or reports why it couldn't.
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, FromBytesError>;
+}
+
+impl FromBytes for String {
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, FromBytesError> {
+        String::from_utf8(bytes).map_err(|e| FromBytesError::InvalidUtf8(e.utf8_error()))
+    }
+}
+
+impl FromBytes for Vec<u8> {
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, FromBytesError> {
+        Ok(bytes)
+    }
+}
+
+impl<const N: usize> FromBytes for [u8; N] {
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, FromBytesError> {
+        let got = bytes.len();
+        bytes
+            .try_into()
+            .map_err(|_| FromBytesError::WrongLength { expected: N, got })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_wtf8_units_encodes_ascii_and_multibyte_utf8() {
+        assert_eq!(encode_wtf8_units([0x0048, 0x0069].into_iter()), b"Hi");
+        assert_eq!(encode_wtf8_units([0x00E9].into_iter()), [0xC3, 0xA9]); // é, 2-byte UTF-8
+        assert_eq!(encode_wtf8_units([0x20AC].into_iter()), [0xE2, 0x82, 0xAC]); // €, 3-byte UTF-8
+        // A surrogate pair for 😀 (U+1F600) must re-combine into one 4-byte UTF-8 sequence.
+        assert_eq!(
+            encode_wtf8_units([0xD83D, 0xDE00].into_iter()),
+            [0xF0, 0x9F, 0x98, 0x80]
+        );
+    }
+
+    #[test]
+    fn encode_wtf8_units_encodes_unpaired_surrogates_as_three_bytes() {
+        // An unpaired high surrogate has no valid UTF-8 form; WTF-8 encodes it as a three-byte
+        // sequence that would otherwise be invalid UTF-8 for that code point.
+        assert_eq!(encode_wtf8_units([0xD800].into_iter()), [0xED, 0xA0, 0x80]);
+        // Likewise for an unpaired low surrogate.
+        assert_eq!(encode_wtf8_units([0xDC00].into_iter()), [0xED, 0xB0, 0x80]);
+    }
+
+    #[test]
+    fn string_from_bytes_accepts_valid_utf8() {
+        assert_eq!(
+            String::from_bytes(b"Hello, world!".to_vec()),
+            Ok("Hello, world!".to_string())
+        );
+    }
+
+    #[test]
+    fn string_from_bytes_rejects_invalid_utf8() {
+        let err = String::from_bytes(vec![0xFF]).unwrap_err();
+        assert!(matches!(err, FromBytesError::InvalidUtf8(_)));
+    }
+
+    #[test]
+    fn vec_from_bytes_is_a_passthrough() {
+        assert_eq!(Vec::<u8>::from_bytes(vec![1, 2, 3]), Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn array_from_bytes_accepts_the_exact_length() {
+        assert_eq!(<[u8; 3]>::from_bytes(vec![1, 2, 3]), Ok([1, 2, 3]));
+    }
+
+    #[test]
+    fn array_from_bytes_rejects_the_wrong_length() {
+        assert_eq!(
+            <[u8; 3]>::from_bytes(vec![1, 2]),
+            Err(FromBytesError::WrongLength { expected: 3, got: 2 })
+        );
     }
 }